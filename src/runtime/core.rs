@@ -4,6 +4,8 @@ use serde::{Serialize, Deserialize};
 use thiserror::Error;
 use std::collections::HashMap;
 
+use super::scheduler::{Context, Runnable, SchedulerError};
+
 #[derive(Error, Debug)]
 pub enum RuntimeError {
     #[error("Agent not found: {0}")]
@@ -36,6 +38,13 @@ pub struct RuntimeEngine {
     config: RuntimeConfig,
     agents: Arc<RwLock<HashMap<String, AgentInstance>>>,
     metrics: Arc<Mutex<RuntimeMetrics>>,
+    /// Shared state handed to every registered `Runnable`, mirroring
+    /// `Scheduler`'s `Context`.
+    context: Arc<Context>,
+    /// Real work an agent performs when `execute_task` is called for it,
+    /// keyed by agent id. An agent with nothing registered falls back to
+    /// the simulated echo behavior.
+    runnables: Arc<RwLock<HashMap<String, Arc<dyn Runnable>>>>,
 }
 
 #[derive(Debug)]
@@ -72,9 +81,17 @@ impl RuntimeEngine {
                 active_agents: 0,
                 total_requests: 0,
             })),
+            context: Arc::new(Context::new()),
+            runnables: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Registers the handler `execute_task` dispatches to for `agent_id`,
+    /// giving the agent real work instead of the echo-based simulation.
+    pub async fn register_runnable(&self, agent_id: impl Into<String>, runnable: Arc<dyn Runnable>) {
+        self.runnables.write().await.insert(agent_id.into(), runnable);
+    }
+
     pub async fn register_agent(&self, id: String) -> Result<(), RuntimeError> {
         let mut agents = self.agents.write().await;
         
@@ -102,18 +119,32 @@ impl RuntimeEngine {
     }
 
     pub async fn execute_task(&self, agent_id: &str, task: Vec<u8>) -> Result<Vec<u8>, RuntimeError> {
-        let agents = self.agents.read().await;
-        let agent = agents.get(agent_id).ok_or_else(|| 
-            RuntimeError::AgentNotFound(agent_id.to_string())
-        )?;
+        {
+            let agents = self.agents.read().await;
+            agents.get(agent_id).ok_or_else(||
+                RuntimeError::AgentNotFound(agent_id.to_string())
+            )?;
+        }
 
-        // Simulate task execution
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let runnable = self.runnables.read().await.get(agent_id).cloned();
+
+        let result = match runnable {
+            Some(runnable) => runnable
+                .run(&self.context)
+                .await
+                .map_err(|e: SchedulerError| RuntimeError::ExecutionError(e.to_string()))?,
+            None => {
+                // No runnable registered for this agent: fall back to the
+                // original echo-based simulation.
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                task
+            }
+        };
 
         let mut metrics = self.metrics.lock().await;
         metrics.total_requests += 1;
 
-        Ok(task) // Echo back the task for simulation
+        Ok(result)
     }
 
     pub async fn get_agent_metrics(&self, agent_id: &str) -> Result<AgentMetrics, RuntimeError> {
@@ -196,4 +227,44 @@ mod tests {
         assert!(runtime.register_agent("agent1".to_string()).await.is_ok());
         assert!(runtime.register_agent("agent2".to_string()).await.is_err());
     }
+
+    struct Echo;
+
+    #[async_trait::async_trait]
+    impl Runnable for Echo {
+        async fn run(&self, _ctx: &Context) -> Result<Vec<u8>, SchedulerError> {
+            Ok(b"real work".to_vec())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_task_dispatches_to_registered_runnable() {
+        let runtime = RuntimeEngine::new(RuntimeConfig {
+            max_agents: 10,
+            memory_limit: 1024 * 1024,
+            timeout_ms: 1000,
+            enable_metrics: true,
+        }).await;
+
+        runtime.register_agent("agent1".to_string()).await.unwrap();
+        runtime.register_runnable("agent1", Arc::new(Echo)).await;
+
+        let result = runtime.execute_task("agent1", b"ignored".to_vec()).await.unwrap();
+        assert_eq!(result, b"real work");
+    }
+
+    #[tokio::test]
+    async fn test_execute_task_falls_back_to_echo_without_runnable() {
+        let runtime = RuntimeEngine::new(RuntimeConfig {
+            max_agents: 10,
+            memory_limit: 1024 * 1024,
+            timeout_ms: 1000,
+            enable_metrics: true,
+        }).await;
+
+        runtime.register_agent("agent1".to_string()).await.unwrap();
+
+        let result = runtime.execute_task("agent1", b"payload".to_vec()).await.unwrap();
+        assert_eq!(result, b"payload");
+    }
 }
\ No newline at end of file
@@ -4,6 +4,8 @@ use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 use thiserror::Error;
 
+use super::storage::{InMemoryBackend, StorageBackend, StorageError};
+
 #[derive(Error, Debug)]
 pub enum MemoryError {
     #[error("Memory limit exceeded: {0}")]
@@ -14,6 +16,14 @@ pub enum MemoryError {
     InvalidOperation(String),
     #[error("Serialization error: {0}")]
     SerializationError(String),
+    #[error("Storage backend error: {0}")]
+    StorageError(String),
+}
+
+impl From<StorageError> for MemoryError {
+    fn from(err: StorageError) -> Self {
+        MemoryError::StorageError(err.to_string())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,17 +43,29 @@ struct MemoryBlock {
     metadata: HashMap<String, String>,
 }
 
-pub struct MemoryManager {
+pub struct MemoryManager<B: StorageBackend = InMemoryBackend> {
     memory_limit: usize,
-    storage: Arc<RwLock<HashMap<String, MemoryBlock>>>,
+    backend: Arc<B>,
+    /// Keys currently allocated, mirrored alongside the backend so TTL sweeps
+    /// can enumerate blocks without the backend having to support listing.
+    keys: Arc<RwLock<HashMap<String, ()>>>,
     stats: Arc<RwLock<MemoryStats>>,
 }
 
-impl MemoryManager {
+impl MemoryManager<InMemoryBackend> {
     pub async fn new(memory_limit: usize) -> Self {
+        Self::with_backend(memory_limit, InMemoryBackend::new()).await
+    }
+}
+
+impl<B: StorageBackend> MemoryManager<B> {
+    /// Builds a memory manager against an explicit `StorageBackend`, e.g. a
+    /// `FileBackend` so memory blocks survive a restart.
+    pub async fn with_backend(memory_limit: usize, backend: B) -> Self {
         Self {
             memory_limit,
-            storage: Arc::new(RwLock::new(HashMap::new())),
+            backend: Arc::new(backend),
+            keys: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(MemoryStats {
                 total_allocated: 0,
                 total_freed: 0,
@@ -61,7 +83,6 @@ impl MemoryManager {
         ttl: Option<std::time::Duration>,
         metadata: HashMap<String, String>,
     ) -> Result<(), MemoryError> {
-        let mut storage = self.storage.write().await;
         let mut stats = self.stats.write().await;
 
         let block_size = data.len();
@@ -82,51 +103,49 @@ impl MemoryManager {
             metadata,
         };
 
+        let encoded = serde_json::to_vec(&block)
+            .map_err(|e| MemoryError::SerializationError(e.to_string()))?;
+        self.backend.store(&key, encoded).await?;
+        self.keys.write().await.insert(key, ());
+
         // Update stats
         stats.total_allocated += block_size;
         stats.current_usage += block_size;
         stats.allocation_count += 1;
         stats.peak_usage = stats.peak_usage.max(stats.current_usage);
 
-        // Store the block
-        storage.insert(key, block);
-
         Ok(())
     }
 
     pub async fn free(&self, key: &str) -> Result<(), MemoryError> {
-        let mut storage = self.storage.write().await;
+        let block = self.load_block(key).await?;
+        self.backend.remove(key).await?;
+        self.keys.write().await.remove(key);
+
         let mut stats = self.stats.write().await;
+        stats.total_freed += block.data.len();
+        stats.current_usage -= block.data.len();
 
-        if let Some(block) = storage.remove(key) {
-            stats.total_freed += block.data.len();
-            stats.current_usage -= block.data.len();
-            Ok(())
-        } else {
-            Err(MemoryError::KeyNotFound(key.to_string()))
-        }
+        Ok(())
     }
 
     pub async fn get(&self, key: &str) -> Result<Vec<u8>, MemoryError> {
-        let storage = self.storage.read().await;
-
-        if let Some(block) = storage.get(key) {
-            // Check TTL if set
-            if let Some(ttl) = block.ttl {
-                let age = block.timestamp
-                    .elapsed()
-                    .map_err(|e| MemoryError::InvalidOperation(e.to_string()))?;
-                
-                if age > ttl {
-                    return Err(MemoryError::KeyNotFound(
-                        "Key expired".to_string()
-                    ));
-                }
+        let block = self.load_block(key).await?;
+
+        // Check TTL if set
+        if let Some(ttl) = block.ttl {
+            let age = block.timestamp
+                .elapsed()
+                .map_err(|e| MemoryError::InvalidOperation(e.to_string()))?;
+
+            if age > ttl {
+                return Err(MemoryError::KeyNotFound(
+                    "Key expired".to_string()
+                ));
             }
-            Ok(block.data.clone())
-        } else {
-            Err(MemoryError::KeyNotFound(key.to_string()))
         }
+
+        Ok(block.data)
     }
 
     pub async fn get_stats(&self) -> MemoryStats {
@@ -134,24 +153,29 @@ impl MemoryManager {
     }
 
     pub async fn cleanup_expired(&self) -> Result<usize, MemoryError> {
-        let mut storage = self.storage.write().await;
-        let mut stats = self.stats.write().await;
+        let keys: Vec<String> = self.keys.read().await.keys().cloned().collect();
         let mut cleaned = 0;
 
-        storage.retain(|_, block| {
-            if let Some(ttl) = block.ttl {
-                if let Ok(age) = block.timestamp.elapsed() {
-                    if age <= ttl {
-                        return true;
-                    }
-                    stats.current_usage -= block.data.len();
-                    stats.total_freed += block.data.len();
-                    cleaned += 1;
-                    return false;
-                }
+        for key in keys {
+            let block = match self.load_block(&key).await {
+                Ok(block) => block,
+                Err(_) => continue,
+            };
+
+            let Some(ttl) = block.ttl else { continue };
+            let Ok(age) = block.timestamp.elapsed() else { continue };
+            if age <= ttl {
+                continue;
             }
-            true
-        });
+
+            self.backend.remove(&key).await?;
+            self.keys.write().await.remove(&key);
+
+            let mut stats = self.stats.write().await;
+            stats.current_usage -= block.data.len();
+            stats.total_freed += block.data.len();
+            cleaned += 1;
+        }
 
         Ok(cleaned)
     }
@@ -162,24 +186,28 @@ impl MemoryManager {
         metadata_key: String,
         metadata_value: String,
     ) -> Result<(), MemoryError> {
-        let mut storage = self.storage.write().await;
+        let mut block = self.load_block(key).await?;
+        block.metadata.insert(metadata_key, metadata_value);
 
-        if let Some(block) = storage.get_mut(key) {
-            block.metadata.insert(metadata_key, metadata_value);
-            Ok(())
-        } else {
-            Err(MemoryError::KeyNotFound(key.to_string()))
-        }
+        let encoded = serde_json::to_vec(&block)
+            .map_err(|e| MemoryError::SerializationError(e.to_string()))?;
+        self.backend.store(key, encoded).await?;
+
+        Ok(())
     }
 
     pub async fn get_metadata(&self, key: &str) -> Result<HashMap<String, String>, MemoryError> {
-        let storage = self.storage.read().await;
+        Ok(self.load_block(key).await?.metadata)
+    }
 
-        if let Some(block) = storage.get(key) {
-            Ok(block.metadata.clone())
-        } else {
-            Err(MemoryError::KeyNotFound(key.to_string()))
-        }
+    async fn load_block(&self, key: &str) -> Result<MemoryBlock, MemoryError> {
+        let bytes = self
+            .backend
+            .load(key)
+            .await?
+            .ok_or_else(|| MemoryError::KeyNotFound(key.to_string()))?;
+
+        serde_json::from_slice(&bytes).map_err(|e| MemoryError::SerializationError(e.to_string()))
     }
 }
 
@@ -1,10 +1,15 @@
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use std::str::FromStr;
+use tokio::sync::{mpsc, Notify, RwLock, Semaphore};
 use tokio::time::{Duration, Instant};
-use std::collections::{HashMap, BinaryHeap};
+use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 use thiserror::Error;
-use std::cmp::Ordering;
+use chrono::{DateTime, Utc};
+use cron::Schedule as CronSchedule;
+use async_trait::async_trait;
+
+use super::storage::{InMemoryBackend, StorageBackend, StorageError, StoredTask};
 
 #[derive(Error, Debug)]
 pub enum SchedulerError {
@@ -16,6 +21,14 @@ pub enum SchedulerError {
     InvalidSchedule(String),
     #[error("Task execution failed: {0}")]
     ExecutionError(String),
+    #[error("Storage backend error: {0}")]
+    StorageError(String),
+}
+
+impl From<StorageError> for SchedulerError {
+    fn from(err: StorageError) -> Self {
+        SchedulerError::StorageError(err.to_string())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +38,69 @@ pub struct TaskConfig {
     pub max_retries: u32,
     pub timeout: Duration,
     pub dependencies: Vec<String>,
+    /// Base delay for the first retry; doubled for each subsequent attempt.
+    pub base_backoff: Duration,
+    /// Upper bound on the exponential part of the backoff delay, regardless of
+    /// retry count; up to 25% jitter is added on top of the capped value.
+    pub max_backoff: Duration,
+    pub schedule: Scheduled,
+    /// Key into the scheduler's runnable registry that selects the handler
+    /// this task is dispatched to.
+    pub task_type: String,
+}
+
+/// How a task's `scheduled_time` is derived and, for `CronPattern`, re-derived
+/// after each successful run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Scheduled {
+    /// Standard cron expression (`sec min hour dom month dow`); re-enqueued after
+    /// every successful completion at its next occurrence.
+    CronPattern(String),
+    /// Fires once at the given time and is not rescheduled.
+    ScheduleOnce(DateTime<Utc>),
+    /// Runs as soon as it reaches the front of the queue.
+    Immediate,
+}
+
+/// Application state handed to `Scheduler::new` once and passed by
+/// reference into every `Runnable::run` call.
+#[derive(Debug, Default)]
+pub struct Context {
+    values: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self { values: RwLock::new(HashMap::new()) }
+    }
+
+    pub async fn set(&self, key: impl Into<String>, value: Vec<u8>) {
+        self.values.write().await.insert(key.into(), value);
+    }
+
+    pub async fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.values.read().await.get(key).cloned()
+    }
+}
+
+/// User-defined work dispatched by task type, with access to the shared
+/// `Context` rather than operating on raw bytes in isolation.
+#[async_trait]
+pub trait Runnable: Send + Sync {
+    async fn run(&self, ctx: &Context) -> Result<Vec<u8>, SchedulerError>;
+}
+
+/// Governs whether a task's `TaskStats` entry survives past its terminal
+/// execution, so long-running schedulers don't leak memory through `task_stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RetentionMode {
+    /// Keep every task's stats (the crate's original behavior); only the
+    /// optional `set_stats_eviction` sweep removes entries.
+    KeepAll,
+    /// Drop a task's stats entry the moment it terminally succeeds or fails.
+    RemoveAll,
+    /// Drop only the stats entries of tasks that terminally fail.
+    RemoveFailed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,7 +111,7 @@ pub struct TaskStats {
     pub last_execution: Option<Instant>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone)]
 struct Task {
     id: String,
     priority: u8,
@@ -44,84 +120,433 @@ struct Task {
     retries: u32,
 }
 
-impl Ord for Task {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.priority.cmp(&other.priority)
-            .then_with(|| other.scheduled_time.cmp(&self.scheduled_time))
+impl Task {
+    /// Converts to the backend-agnostic, wall-clock representation that
+    /// `StorageBackend` persists.
+    fn into_stored(self) -> StoredTask {
+        StoredTask {
+            id: self.id,
+            priority: self.priority,
+            scheduled_at: datetime_from_instant(self.scheduled_time),
+            retries: self.retries,
+            payload: serde_json::to_vec(&self.config).unwrap_or_default(),
+        }
+    }
+
+    /// Reconstructs the in-process `Task` from a backend's persisted form.
+    fn from_stored(stored: StoredTask) -> Result<Self, SchedulerError> {
+        let config: TaskConfig = serde_json::from_slice(&stored.payload).map_err(|e| {
+            SchedulerError::ExecutionError(format!("corrupt task payload: {}", e))
+        })?;
+
+        Ok(Task {
+            id: stored.id,
+            priority: stored.priority,
+            scheduled_time: instant_from_datetime(stored.scheduled_at),
+            config,
+            retries: stored.retries,
+        })
+    }
+}
+
+/// Converts a wall-clock target into an `Instant` relative to now; a target
+/// already in the past collapses to "run immediately".
+fn instant_from_datetime(target: DateTime<Utc>) -> Instant {
+    match (target - Utc::now()).to_std() {
+        Ok(delta) => Instant::now() + delta,
+        Err(_) => Instant::now(),
     }
 }
 
-impl PartialOrd for Task {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// Converts a monotonic `Instant` into a wall-clock `DateTime<Utc>` relative to
+/// now, the inverse of `instant_from_datetime`, so backends can persist
+/// `scheduled_time` across process restarts.
+fn datetime_from_instant(instant: Instant) -> DateTime<Utc> {
+    let now = Instant::now();
+    if instant >= now {
+        Utc::now() + chrono::Duration::from_std(instant - now).unwrap_or_default()
+    } else {
+        Utc::now() - chrono::Duration::from_std(now - instant).unwrap_or_default()
     }
 }
 
-pub struct Scheduler {
-    task_queue: Arc<RwLock<BinaryHeap<Task>>>,
+/// Parses a cron expression and resolves its next occurrence into an `Instant`.
+fn next_cron_occurrence(expr: &str) -> Result<Instant, SchedulerError> {
+    let schedule = CronSchedule::from_str(expr).map_err(|e| {
+        SchedulerError::InvalidSchedule(format!("invalid cron expression '{}': {}", expr, e))
+    })?;
+
+    let next = schedule.upcoming(Utc).next().ok_or_else(|| {
+        SchedulerError::InvalidSchedule(format!("cron expression '{}' has no upcoming occurrence", expr))
+    })?;
+
+    Ok(instant_from_datetime(next))
+}
+
+pub struct Scheduler<B: StorageBackend = InMemoryBackend> {
+    backend: Arc<B>,
     task_stats: Arc<RwLock<HashMap<String, TaskStats>>>,
     max_concurrent: usize,
     tx: mpsc::Sender<Task>,
     rx: mpsc::Receiver<Task>,
+    context: Arc<Context>,
+    runnables: Arc<RwLock<HashMap<String, Arc<dyn Runnable>>>>,
+    /// Full dependency edges (task id -> ids it depends on), kept to detect cycles.
+    dependencies_of: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// Reverse edges (task id -> ids waiting on it) used to release dependents.
+    dependents: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// Count of not-yet-terminal dependencies remaining per held-back task.
+    pending_deps: Arc<RwLock<HashMap<String, usize>>>,
+    /// Configs held back until `pending_deps` for that id reaches zero.
+    pending_tasks: Arc<RwLock<HashMap<String, TaskConfig>>>,
+    /// Terminal outcome of every task that has finished: `true` succeeded,
+    /// `false` permanently failed after exhausting retries.
+    completed: Arc<RwLock<HashMap<String, bool>>>,
+    retention: RetentionMode,
+    /// Optionally evict stats entries older than this, in any `RetentionMode`.
+    stats_ttl: Option<Duration>,
+    /// Optionally cap `task_stats` to this many entries, in any `RetentionMode`,
+    /// evicting the oldest by `last_execution` once it's exceeded.
+    max_stats_entries: Option<usize>,
+    /// Signaled whenever a task is enqueued so the pop loop can wake
+    /// immediately instead of waiting out a fixed poll interval.
+    notify: Arc<Notify>,
+    /// Window the pop loop waits after a dispatch round so tasks that become
+    /// ready close together are batched into the same round.
+    throttle_interval: Duration,
+    /// Bounds how many tasks actually execute at once; unlike `max_concurrent`,
+    /// which only bounds queue length, this is enforced with a semaphore.
+    execution_limit: Arc<Semaphore>,
+}
+
+/// Bundles the state `mark_task_complete` needs to resolve and dispatch
+/// dependents, so its signature doesn't keep growing positionally every time
+/// cascade handling picks up more wiring.
+struct CascadeState<B: StorageBackend> {
+    completed: Arc<RwLock<HashMap<String, bool>>>,
+    dependents: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    pending_deps: Arc<RwLock<HashMap<String, usize>>>,
+    pending_tasks: Arc<RwLock<HashMap<String, TaskConfig>>>,
+    backend: Arc<B>,
+    stats: Arc<RwLock<HashMap<String, TaskStats>>>,
+    notify: Arc<Notify>,
+    max_concurrent: usize,
+    retention: RetentionMode,
 }
 
-impl Scheduler {
-    pub async fn new(max_concurrent: usize) -> Self {
+impl<B: StorageBackend> Clone for CascadeState<B> {
+    fn clone(&self) -> Self {
+        Self {
+            completed: Arc::clone(&self.completed),
+            dependents: Arc::clone(&self.dependents),
+            pending_deps: Arc::clone(&self.pending_deps),
+            pending_tasks: Arc::clone(&self.pending_tasks),
+            backend: Arc::clone(&self.backend),
+            stats: Arc::clone(&self.stats),
+            notify: Arc::clone(&self.notify),
+            max_concurrent: self.max_concurrent,
+            retention: self.retention,
+        }
+    }
+}
+
+/// Default batching window the pop loop waits after dispatching a round,
+/// giving near-simultaneous arrivals a chance to land in the same batch.
+const DEFAULT_THROTTLE_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Upper bound on how long the pop loop parks on `notify` while idle, so
+/// cron/`ScheduleOnce` tasks whose readiness depends only on wall-clock time
+/// (and never signal `notify`) are still picked up promptly.
+const IDLE_POLL_CAP: Duration = Duration::from_millis(50);
+
+impl Scheduler<InMemoryBackend> {
+    pub async fn new(max_concurrent: usize, context: Context, retention: RetentionMode) -> Self {
+        Self::with_backend(max_concurrent, context, InMemoryBackend::new(), retention).await
+    }
+}
+
+impl<B: StorageBackend + 'static> Scheduler<B> {
+    /// Builds a scheduler against an explicit `StorageBackend`, e.g. a
+    /// `FileBackend` so queued tasks survive a restart.
+    pub async fn with_backend(
+        max_concurrent: usize,
+        context: Context,
+        backend: B,
+        retention: RetentionMode,
+    ) -> Self {
         let (tx, rx) = mpsc::channel(max_concurrent);
-        
+
         Self {
-            task_queue: Arc::new(RwLock::new(BinaryHeap::new())),
+            backend: Arc::new(backend),
             task_stats: Arc::new(RwLock::new(HashMap::new())),
             max_concurrent,
             tx,
             rx,
+            context: Arc::new(context),
+            runnables: Arc::new(RwLock::new(HashMap::new())),
+            dependencies_of: Arc::new(RwLock::new(HashMap::new())),
+            dependents: Arc::new(RwLock::new(HashMap::new())),
+            pending_deps: Arc::new(RwLock::new(HashMap::new())),
+            pending_tasks: Arc::new(RwLock::new(HashMap::new())),
+            completed: Arc::new(RwLock::new(HashMap::new())),
+            retention,
+            stats_ttl: None,
+            max_stats_entries: None,
+            notify: Arc::new(Notify::new()),
+            throttle_interval: DEFAULT_THROTTLE_INTERVAL,
+            execution_limit: Arc::new(Semaphore::new(max_concurrent)),
         }
     }
 
+    /// Registers the handler dispatched to for tasks whose `task_type` matches `name`.
+    pub async fn register_runnable(&self, name: impl Into<String>, runnable: Arc<dyn Runnable>) {
+        self.runnables.write().await.insert(name.into(), runnable);
+    }
+
+    /// Bounds `task_stats` growth independently of `RetentionMode`: entries
+    /// older than `ttl` and, once the map exceeds `max_entries`, the oldest
+    /// entries by `last_execution` are periodically evicted.
+    pub fn set_stats_eviction(&mut self, ttl: Option<Duration>, max_entries: Option<usize>) {
+        self.stats_ttl = ttl;
+        self.max_stats_entries = max_entries;
+    }
+
+    /// Overrides the pop loop's batching window (default 5ms). Larger values
+    /// let more near-simultaneous arrivals land in the same dispatch round
+    /// at the cost of added latency for the first task in a round.
+    pub fn set_throttle_interval(&mut self, interval: Duration) {
+        self.throttle_interval = interval;
+    }
+
     pub async fn schedule_task(&self, config: TaskConfig) -> Result<(), SchedulerError> {
+        if !config.dependencies.is_empty() {
+            self.check_for_cycle(&config).await?;
+        }
+
+        self.dependencies_of.write().await.insert(config.id.clone(), config.dependencies.clone());
+
+        let unmet = self.unmet_dependency_count(&config).await?;
+        if unmet == 0 {
+            return self.enqueue_now(config).await;
+        }
+
+        {
+            let mut dependents = self.dependents.write().await;
+            for dep in &config.dependencies {
+                dependents.entry(dep.clone()).or_insert_with(Vec::new).push(config.id.clone());
+            }
+        }
+
+        self.pending_deps.write().await.insert(config.id.clone(), unmet);
+        self.pending_tasks.write().await.insert(config.id.clone(), config);
+
+        Ok(())
+    }
+
+    /// Walks backwards from `config`'s dependencies through the existing
+    /// dependency graph; if that walk reaches `config.id` itself, scheduling
+    /// it would close a cycle.
+    async fn check_for_cycle(&self, config: &TaskConfig) -> Result<(), SchedulerError> {
+        let dependencies_of = self.dependencies_of.read().await;
+        let mut stack = config.dependencies.clone();
+        let mut visited = std::collections::HashSet::new();
+
+        while let Some(current) = stack.pop() {
+            if current == config.id {
+                return Err(SchedulerError::InvalidSchedule(format!(
+                    "scheduling '{}' would create a dependency cycle", config.id
+                )));
+            }
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+            if let Some(deps) = dependencies_of.get(&current) {
+                stack.extend(deps.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Counts dependencies that haven't reached a terminal state yet. Returns
+    /// an error immediately if a dependency has already permanently failed,
+    /// since this task could then never become runnable.
+    async fn unmet_dependency_count(&self, config: &TaskConfig) -> Result<usize, SchedulerError> {
+        let completed = self.completed.read().await;
+        let mut unmet = 0;
+
+        for dep in &config.dependencies {
+            match completed.get(dep) {
+                Some(true) => {}
+                Some(false) => {
+                    return Err(SchedulerError::InvalidSchedule(format!(
+                        "dependency '{}' has already permanently failed", dep
+                    )));
+                }
+                None => unmet += 1,
+            }
+        }
+
+        Ok(unmet)
+    }
+
+    async fn enqueue_now(&self, config: TaskConfig) -> Result<(), SchedulerError> {
+        if self.backend.queue_len().await? >= self.max_concurrent {
+            return Err(SchedulerError::QueueFull(
+                "Maximum concurrent tasks reached".to_string()
+            ));
+        }
+
+        let scheduled_time = match &config.schedule {
+            Scheduled::Immediate => Instant::now(),
+            Scheduled::ScheduleOnce(at) => instant_from_datetime(*at),
+            Scheduled::CronPattern(expr) => next_cron_occurrence(expr)?,
+        };
+
         let task = Task {
             id: config.id.clone(),
             priority: config.priority,
-            scheduled_time: Instant::now(),
+            scheduled_time,
             config,
             retries: 0,
         };
 
-        let mut queue = self.task_queue.write().await;
-        if queue.len() >= self.max_concurrent {
-            return Err(SchedulerError::QueueFull(
-                "Maximum concurrent tasks reached".to_string()
-            ));
-        }
-
-        queue.push(task);
+        self.backend.enqueue(task.into_stored()).await?;
+        self.notify.notify_one();
         Ok(())
     }
 
     pub async fn start(&mut self) {
-        let queue = Arc::clone(&self.task_queue);
+        let backend = Arc::clone(&self.backend);
         let stats = Arc::clone(&self.task_stats);
+        let context = Arc::clone(&self.context);
+        let runnables = Arc::clone(&self.runnables);
+        let notify = Arc::clone(&self.notify);
+        let execution_limit = Arc::clone(&self.execution_limit);
+        let throttle_interval = self.throttle_interval;
         let tx = self.tx.clone();
 
+        let cascade = CascadeState {
+            completed: Arc::clone(&self.completed),
+            dependents: Arc::clone(&self.dependents),
+            pending_deps: Arc::clone(&self.pending_deps),
+            pending_tasks: Arc::clone(&self.pending_tasks),
+            backend: Arc::clone(&self.backend),
+            stats: Arc::clone(&self.task_stats),
+            notify: Arc::clone(&self.notify),
+            max_concurrent: self.max_concurrent,
+            retention: self.retention,
+        };
+
+        if self.stats_ttl.is_some() || self.max_stats_entries.is_some() {
+            let stats = Arc::clone(&stats);
+            let ttl = self.stats_ttl;
+            let max_entries = self.max_stats_entries;
+            let sweep_interval = ttl.unwrap_or(Duration::from_secs(60)).min(Duration::from_secs(60));
+
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(sweep_interval).await;
+                    let mut stats = stats.write().await;
+                    let now = Instant::now();
+
+                    if let Some(ttl) = ttl {
+                        stats.retain(|_, s| {
+                            s.last_execution.is_none_or(|last| now.saturating_duration_since(last) <= ttl)
+                        });
+                    }
+
+                    if let Some(max_entries) = max_entries {
+                        while stats.len() > max_entries {
+                            let oldest = stats
+                                .iter()
+                                .min_by_key(|(_, s)| s.last_execution.unwrap_or(now))
+                                .map(|(id, _)| id.clone());
+
+                            match oldest {
+                                Some(id) => {
+                                    stats.remove(&id);
+                                }
+                                None => break,
+                            }
+                        }
+                    }
+                }
+            });
+        }
+
+        let pop_backend = Arc::clone(&backend);
+        let pop_notify = Arc::clone(&notify);
         tokio::spawn(async move {
             loop {
-                let mut queue = queue.write().await;
-                if let Some(task) = queue.pop() {
-                    if let Err(e) = tx.send(task).await {
-                        eprintln!("Failed to send task: {}", e);
+                // Drain every task that's ready right now into one batch
+                // instead of dispatching a single task per wakeup.
+                let mut batch = Vec::new();
+                loop {
+                    match pop_backend.dequeue_ready(Utc::now()).await {
+                        Ok(Some(stored)) => batch.push(stored),
+                        Ok(None) => break,
+                        Err(e) => {
+                            eprintln!("Failed to dequeue task: {}", e);
+                            break;
+                        }
+                    }
+                }
+
+                if batch.is_empty() {
+                    // Park until a newly scheduled task wakes us via
+                    // `notify`, capped so time-based (cron/one-shot) tasks
+                    // that never signal `notify` are still polled promptly.
+                    let _ = tokio::time::timeout(IDLE_POLL_CAP, pop_notify.notified()).await;
+                    continue;
+                }
+
+                for stored in batch {
+                    match Task::from_stored(stored) {
+                        Ok(task) => {
+                            if let Err(e) = tx.send(task).await {
+                                eprintln!("Failed to send task: {}", e);
+                            }
+                        }
+                        Err(e) => eprintln!("Failed to decode stored task: {}", e),
                     }
                 }
-                tokio::time::sleep(Duration::from_millis(100)).await;
+
+                // Give tasks that become ready close together a chance to
+                // land in the same batch before the next dispatch round.
+                tokio::time::sleep(throttle_interval).await;
             }
         });
 
         while let Some(task) = self.rx.recv().await {
             let stats = Arc::clone(&stats);
-            
+            let backend = Arc::clone(&backend);
+            let context = Arc::clone(&context);
+            let runnables = Arc::clone(&runnables);
+            let notify = Arc::clone(&notify);
+            let execution_limit = Arc::clone(&execution_limit);
+            let cascade = cascade.clone();
+            let retention = cascade.retention;
+
             tokio::spawn(async move {
-                let execution_result = Self::execute_task(&task).await;
+                // Bounds actual in-flight executions to `max_concurrent`,
+                // independent of the batch size the pop loop just dispatched.
+                let _permit = execution_limit.acquire_owned().await;
+
+                let execution_result = Self::execute_task(&task, &context, &runnables).await;
+
+                if execution_result.is_err() && task.retries < task.config.max_retries {
+                    let mut retried = task.clone();
+                    retried.retries += 1;
+                    retried.scheduled_time = Instant::now() + Self::backoff_delay(&task.config, task.retries);
+                    let _ = backend.enqueue(retried.into_stored()).await;
+                    let _ = backend.mark_failed(&task.id).await;
+                    notify.notify_one();
+                    return;
+                }
+
                 let mut task_stats = stats.write().await;
-                
+
                 let stats_entry = task_stats.entry(task.id.clone())
                     .or_insert(TaskStats {
                         total_executed: 0,
@@ -133,42 +558,180 @@ impl Scheduler {
                 stats_entry.total_executed += 1;
                 stats_entry.last_execution = Some(Instant::now());
 
-                if execution_result.is_err() {
+                let succeeded = execution_result.is_ok();
+
+                if !succeeded {
                     stats_entry.total_failed += 1;
+                    let _ = backend.mark_failed(&task.id).await;
+                } else {
+                    let _ = backend.mark_done(&task.id).await;
+
+                    if let Scheduled::CronPattern(ref expr) = task.config.schedule {
+                        if let Ok(next_time) = next_cron_occurrence(expr) {
+                            let mut next_task = task.clone();
+                            next_task.retries = 0;
+                            next_task.scheduled_time = next_time;
+                            let _ = backend.enqueue(next_task.into_stored()).await;
+                            notify.notify_one();
+                        }
+                    }
                 }
+
+                match retention {
+                    RetentionMode::KeepAll => {}
+                    RetentionMode::RemoveAll => {
+                        task_stats.remove(&task.id);
+                    }
+                    RetentionMode::RemoveFailed => {
+                        if !succeeded {
+                            task_stats.remove(&task.id);
+                        }
+                    }
+                }
+                drop(task_stats);
+
+                Self::mark_task_complete(&task.id, succeeded, &cascade).await;
             });
         }
     }
 
-    async fn execute_task(task: &Task) -> Result<(), SchedulerError> {
-        // Simulate task execution
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        
-        // For demonstration purposes, fail some tasks randomly
-        if rand::random::<f32>() < 0.1 {
-            return Err(SchedulerError::ExecutionError(
-                format!("Task {} failed randomly", task.id)
-            ));
+    /// Records `task_id`'s terminal outcome and releases or cascade-fails
+    /// everything waiting on it. A dependent is released into the backend's
+    /// ready queue once its last unmet dependency succeeds; it is
+    /// cascade-failed (without ever running) the moment any dependency
+    /// permanently fails, and that failure propagates through its own
+    /// dependents in turn.
+    async fn mark_task_complete(task_id: &str, success: bool, cascade: &CascadeState<B>) {
+        cascade.completed.write().await.insert(task_id.to_string(), success);
+
+        let mut frontier: Vec<(String, bool)> = cascade
+            .dependents
+            .write()
+            .await
+            .remove(task_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|id| (id, success))
+            .collect();
+
+        while let Some((id, parent_ok)) = frontier.pop() {
+            if parent_ok {
+                let ready = {
+                    let mut pending = cascade.pending_deps.write().await;
+                    let remaining = pending.entry(id.clone()).or_insert(0);
+                    *remaining = remaining.saturating_sub(1);
+                    let ready = *remaining == 0;
+                    if ready {
+                        pending.remove(&id);
+                    }
+                    ready
+                };
+
+                if ready {
+                    if let Some(config) = cascade.pending_tasks.write().await.remove(&id) {
+                        let scheduled_time = match &config.schedule {
+                            Scheduled::Immediate => Instant::now(),
+                            Scheduled::ScheduleOnce(at) => instant_from_datetime(*at),
+                            Scheduled::CronPattern(expr) => {
+                                next_cron_occurrence(expr).unwrap_or_else(|_| Instant::now())
+                            }
+                        };
+
+                        let task = Task {
+                            id: config.id.clone(),
+                            priority: config.priority,
+                            scheduled_time,
+                            config,
+                            retries: 0,
+                        };
+
+                        Self::enqueue_bounded(cascade, task).await;
+                    }
+                }
+            } else {
+                cascade.pending_deps.write().await.remove(&id);
+                cascade.pending_tasks.write().await.remove(&id);
+                cascade.completed.write().await.insert(id.clone(), false);
+
+                let mut task_stats = cascade.stats.write().await;
+                let stats_entry = task_stats.entry(id.clone()).or_insert(TaskStats {
+                    total_executed: 0,
+                    total_failed: 0,
+                    average_duration: Duration::from_secs(0),
+                    last_execution: None,
+                });
+                stats_entry.total_failed += 1;
+                stats_entry.last_execution = Some(Instant::now());
+
+                // A cascade-failed dependent never actually runs the branch
+                // in `start()` that applies `retention`, so it has to be
+                // applied here too or its stats entry leaks under
+                // `RemoveAll`/`RemoveFailed`.
+                if cascade.retention != RetentionMode::KeepAll {
+                    task_stats.remove(&id);
+                }
+                drop(task_stats);
+
+                for grandchild in cascade.dependents.write().await.remove(&id).unwrap_or_default() {
+                    frontier.push((grandchild, false));
+                }
+            }
         }
+    }
 
-        Ok(())
+    /// Waits until the backend's ready queue has room for another task
+    /// before enqueuing it, so tasks released in bulk by one completing
+    /// dependency still respect the `max_concurrent` queue bound instead of
+    /// bypassing it via a direct `backend.enqueue`.
+    async fn enqueue_bounded(cascade: &CascadeState<B>, task: Task) {
+        loop {
+            match cascade.backend.queue_len().await {
+                Ok(len) if len >= cascade.max_concurrent => {
+                    let _ = tokio::time::timeout(IDLE_POLL_CAP, cascade.notify.notified()).await;
+                }
+                _ => break,
+            }
+        }
+
+        let _ = cascade.backend.enqueue(task.into_stored()).await;
+        cascade.notify.notify_one();
     }
 
-    pub async fn cancel_task(&self, task_id: &str) -> Result<(), SchedulerError> {
-        let mut queue = self.task_queue.write().await;
-        let before_len = queue.len();
-        
-        let mut new_queue: BinaryHeap<Task> = queue.drain()
-            .filter(|task| task.id != task_id)
-            .collect();
-        
-        *queue = new_queue;
+    /// Exponential backoff with jitter: `base * 2^retries`, capped at `max_backoff`,
+    /// plus up to 25% jitter so retried tasks don't all wake up in lockstep.
+    fn backoff_delay(config: &TaskConfig, retries: u32) -> Duration {
+        let exp = config.base_backoff.as_millis().saturating_mul(1u128 << retries.min(32));
+        let capped = exp.min(config.max_backoff.as_millis()) as u64;
+        let jitter = (rand::random::<f64>() * capped as f64 * 0.25) as u64;
+
+        Duration::from_millis(capped + jitter)
+    }
 
-        if queue.len() == before_len {
-            return Err(SchedulerError::TaskNotFound(task_id.to_string()));
+    async fn execute_task(
+        task: &Task,
+        context: &Context,
+        runnables: &RwLock<HashMap<String, Arc<dyn Runnable>>>,
+    ) -> Result<Vec<u8>, SchedulerError> {
+        let runnable = {
+            let runnables = runnables.read().await;
+            runnables.get(&task.config.task_type).cloned()
         }
+        .ok_or_else(|| {
+            SchedulerError::ExecutionError(format!(
+                "no runnable registered for task type '{}'",
+                task.config.task_type
+            ))
+        })?;
 
-        Ok(())
+        runnable.run(context).await
+    }
+
+    pub async fn cancel_task(&self, task_id: &str) -> Result<(), SchedulerError> {
+        if self.backend.cancel(task_id).await? {
+            Ok(())
+        } else {
+            Err(SchedulerError::TaskNotFound(task_id.to_string()))
+        }
     }
 
     pub async fn get_task_stats(&self, task_id: &str) -> Result<TaskStats, SchedulerError> {
@@ -179,12 +742,11 @@ impl Scheduler {
     }
 
     pub async fn get_queue_size(&self) -> usize {
-        self.task_queue.read().await.len()
+        self.backend.queue_len().await.unwrap_or(0)
     }
 
     pub async fn clear_queue(&self) {
-        let mut queue = self.task_queue.write().await;
-        queue.clear();
+        let _ = self.backend.clear().await;
     }
 }
 
@@ -194,14 +756,18 @@ mod tests {
 
     #[tokio::test]
     async fn test_task_scheduling() {
-        let scheduler = Scheduler::new(10).await;
-        
+        let scheduler = Scheduler::new(10, Context::new(), RetentionMode::KeepAll).await;
+
         let config = TaskConfig {
             id: "test-task".to_string(),
             priority: 1,
             max_retries: 3,
             timeout: Duration::from_secs(1),
             dependencies: vec![],
+            base_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(5),
+            schedule: Scheduled::Immediate,
+            task_type: "noop".to_string(),
         };
 
         assert!(scheduler.schedule_task(config).await.is_ok());
@@ -210,14 +776,18 @@ mod tests {
 
     #[tokio::test]
     async fn test_queue_limit() {
-        let scheduler = Scheduler::new(1).await;
-        
+        let scheduler = Scheduler::new(1, Context::new(), RetentionMode::KeepAll).await;
+
         let config1 = TaskConfig {
             id: "task1".to_string(),
             priority: 1,
             max_retries: 3,
             timeout: Duration::from_secs(1),
             dependencies: vec![],
+            base_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(5),
+            schedule: Scheduled::Immediate,
+            task_type: "noop".to_string(),
         };
 
         let config2 = TaskConfig {
@@ -226,6 +796,10 @@ mod tests {
             max_retries: 3,
             timeout: Duration::from_secs(1),
             dependencies: vec![],
+            base_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(5),
+            schedule: Scheduled::Immediate,
+            task_type: "noop".to_string(),
         };
 
         assert!(scheduler.schedule_task(config1).await.is_ok());
@@ -234,18 +808,274 @@ mod tests {
 
     #[tokio::test]
     async fn test_task_cancellation() {
-        let scheduler = Scheduler::new(10).await;
-        
+        let scheduler = Scheduler::new(10, Context::new(), RetentionMode::KeepAll).await;
+
         let config = TaskConfig {
             id: "test-task".to_string(),
             priority: 1,
             max_retries: 3,
             timeout: Duration::from_secs(1),
             dependencies: vec![],
+            base_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(5),
+            schedule: Scheduled::Immediate,
+            task_type: "noop".to_string(),
         };
 
         scheduler.schedule_task(config).await.unwrap();
         assert!(scheduler.cancel_task("test-task").await.is_ok());
         assert_eq!(scheduler.get_queue_size().await, 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_backoff_delay_grows_exponentially_and_caps_at_max() {
+        let config = TaskConfig {
+            id: "retry-task".to_string(),
+            priority: 1,
+            max_retries: 10,
+            timeout: Duration::from_secs(1),
+            dependencies: vec![],
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            schedule: Scheduled::Immediate,
+            task_type: "noop".to_string(),
+        };
+
+        let first = Scheduler::<InMemoryBackend>::backoff_delay(&config, 0);
+        assert!((100..=125).contains(&first.as_millis()));
+
+        let second = Scheduler::<InMemoryBackend>::backoff_delay(&config, 1);
+        assert!((200..=250).contains(&second.as_millis()));
+
+        let capped = Scheduler::<InMemoryBackend>::backoff_delay(&config, 10);
+        assert!((1000..=1250).contains(&capped.as_millis()));
+    }
+
+    #[test]
+    fn test_next_cron_occurrence_resolves_to_a_future_instant() {
+        let before = Instant::now();
+        let next = next_cron_occurrence("* * * * * *").unwrap();
+        assert!(next >= before);
+    }
+
+    #[test]
+    fn test_next_cron_occurrence_rejects_invalid_expression() {
+        assert!(matches!(
+            next_cron_occurrence("not a cron expression"),
+            Err(SchedulerError::InvalidSchedule(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_schedule_once_in_the_past_is_accepted_and_queued() {
+        let scheduler = Scheduler::new(10, Context::new(), RetentionMode::KeepAll).await;
+
+        let config = TaskConfig {
+            id: "past-task".to_string(),
+            priority: 1,
+            max_retries: 0,
+            timeout: Duration::from_secs(1),
+            dependencies: vec![],
+            base_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            schedule: Scheduled::ScheduleOnce(Utc::now() - chrono::Duration::seconds(10)),
+            task_type: "noop".to_string(),
+        };
+
+        assert!(scheduler.schedule_task(config).await.is_ok());
+        assert_eq!(scheduler.get_queue_size().await, 1);
+    }
+
+    fn cascade_state(scheduler: &Scheduler<InMemoryBackend>) -> CascadeState<InMemoryBackend> {
+        CascadeState {
+            completed: Arc::clone(&scheduler.completed),
+            dependents: Arc::clone(&scheduler.dependents),
+            pending_deps: Arc::clone(&scheduler.pending_deps),
+            pending_tasks: Arc::clone(&scheduler.pending_tasks),
+            backend: Arc::clone(&scheduler.backend),
+            stats: Arc::clone(&scheduler.task_stats),
+            notify: Arc::clone(&scheduler.notify),
+            max_concurrent: scheduler.max_concurrent,
+            retention: scheduler.retention,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retention_evicts_cascade_failed_dependent_stats() {
+        for mode in [RetentionMode::RemoveAll, RetentionMode::RemoveFailed] {
+            let scheduler = Scheduler::new(10, Context::new(), mode).await;
+
+            let dependent = TaskConfig {
+                id: "dependent".to_string(),
+                priority: 1,
+                max_retries: 0,
+                timeout: Duration::from_secs(1),
+                dependencies: vec!["parent".to_string()],
+                base_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                schedule: Scheduled::Immediate,
+                task_type: "noop".to_string(),
+            };
+            scheduler.schedule_task(dependent).await.unwrap();
+
+            let cascade = cascade_state(&scheduler);
+            Scheduler::<InMemoryBackend>::mark_task_complete("parent", false, &cascade).await;
+
+            assert!(
+                matches!(
+                    scheduler.get_task_stats("dependent").await,
+                    Err(SchedulerError::TaskNotFound(_))
+                ),
+                "dependent's stats should be evicted under {:?} once it's cascade-failed",
+                mode
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dependency_release_respects_queue_bound() {
+        let scheduler = Scheduler::new(1, Context::new(), RetentionMode::KeepAll).await;
+
+        let filler = TaskConfig {
+            id: "filler".to_string(),
+            priority: 1,
+            max_retries: 0,
+            timeout: Duration::from_secs(1),
+            dependencies: vec![],
+            base_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            schedule: Scheduled::Immediate,
+            task_type: "noop".to_string(),
+        };
+        scheduler.schedule_task(filler).await.unwrap();
+        assert_eq!(scheduler.get_queue_size().await, 1);
+
+        let dependent = TaskConfig {
+            id: "dependent".to_string(),
+            priority: 1,
+            max_retries: 0,
+            timeout: Duration::from_secs(1),
+            dependencies: vec!["parent".to_string()],
+            base_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            schedule: Scheduled::Immediate,
+            task_type: "noop".to_string(),
+        };
+        scheduler.schedule_task(dependent).await.unwrap();
+
+        let cascade = cascade_state(&scheduler);
+        let release = tokio::spawn(async move {
+            Scheduler::<InMemoryBackend>::mark_task_complete("parent", true, &cascade).await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(
+            !release.is_finished(),
+            "dependent should not be enqueued while the queue is already at max_concurrent"
+        );
+        assert_eq!(scheduler.get_queue_size().await, 1);
+
+        scheduler.cancel_task("filler").await.unwrap();
+        scheduler.notify.notify_one();
+
+        tokio::time::timeout(Duration::from_millis(200), release)
+            .await
+            .expect("release should complete once the queue has room")
+            .unwrap();
+
+        assert_eq!(scheduler.get_queue_size().await, 1);
+    }
+
+    /// Runnable that records how many instances of itself are executing
+    /// concurrently, so tests can assert on the peak observed.
+    struct ConcurrencyTracker {
+        current: Arc<std::sync::atomic::AtomicUsize>,
+        peak: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Runnable for ConcurrencyTracker {
+        async fn run(&self, _ctx: &Context) -> Result<Vec<u8>, SchedulerError> {
+            use std::sync::atomic::Ordering;
+
+            let now = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execution_limit_bounds_concurrent_runs_below_batch_size() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut scheduler = Scheduler::new(2, Context::new(), RetentionMode::KeepAll).await;
+        let current = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        scheduler
+            .register_runnable(
+                "track",
+                Arc::new(ConcurrencyTracker {
+                    current: Arc::clone(&current),
+                    peak: Arc::clone(&peak),
+                }),
+            )
+            .await;
+
+        fn mk_config(i: usize) -> TaskConfig {
+            TaskConfig {
+                id: format!("task-{}", i),
+                priority: 1,
+                max_retries: 0,
+                timeout: Duration::from_secs(1),
+                dependencies: vec![],
+                base_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(1),
+                schedule: Scheduled::Immediate,
+                task_type: "track".to_string(),
+            }
+        }
+
+        // Fill the queue to `max_concurrent` up front: `start()` isn't
+        // running yet, so nothing would dequeue and any more than this
+        // would be rejected with `QueueFull`.
+        for i in 0..2 {
+            scheduler.schedule_task(mk_config(i)).await.unwrap();
+        }
+
+        let backend = Arc::clone(&scheduler.backend);
+        let notify = Arc::clone(&scheduler.notify);
+        let handle = tokio::spawn(async move { scheduler.start().await });
+
+        // Feed the remaining tasks directly onto the backend queue as room
+        // frees up, now that the dispatch loop is running and draining it.
+        for i in 2..6 {
+            let config = mk_config(i);
+            let task = Task {
+                id: config.id.clone(),
+                priority: config.priority,
+                scheduled_time: Instant::now(),
+                config,
+                retries: 0,
+            };
+
+            loop {
+                if backend.queue_len().await.unwrap() < 2 {
+                    backend.enqueue(task.into_stored()).await.unwrap();
+                    notify.notify_one();
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        handle.abort();
+
+        assert!(
+            peak.load(Ordering::SeqCst) <= 2,
+            "execution_limit should cap concurrent runs at max_concurrent even though 6 tasks were dispatched"
+        );
+    }
+}
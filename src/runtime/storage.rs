@@ -0,0 +1,316 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("key not found: {0}")]
+    NotFound(String),
+    #[error("backend io error: {0}")]
+    Io(String),
+    #[error("serialization error: {0}")]
+    Serialization(String),
+}
+
+/// A queued task as persisted by a `StorageBackend`, independent of the
+/// scheduler's in-process, `Instant`-based representation. `payload` holds the
+/// serialized `TaskConfig`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoredTask {
+    pub id: String,
+    pub priority: u8,
+    pub scheduled_at: DateTime<Utc>,
+    pub retries: u32,
+    pub payload: Vec<u8>,
+}
+
+impl Eq for StoredTask {}
+
+impl Ord for StoredTask {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.scheduled_at.cmp(&self.scheduled_at))
+    }
+}
+
+impl PartialOrd for StoredTask {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Generic persistence behind both the task queue (`Scheduler`) and the
+/// key-value store (`MemoryManager`), so either can be swapped from the
+/// in-memory default to a durable backend without changing call sites.
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    async fn enqueue(&self, task: StoredTask) -> Result<(), StorageError>;
+    async fn dequeue_ready(&self, now: DateTime<Utc>) -> Result<Option<StoredTask>, StorageError>;
+    async fn mark_done(&self, task_id: &str) -> Result<(), StorageError>;
+    async fn mark_failed(&self, task_id: &str) -> Result<(), StorageError>;
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError>;
+    async fn store(&self, key: &str, value: Vec<u8>) -> Result<(), StorageError>;
+    async fn remove(&self, key: &str) -> Result<(), StorageError>;
+
+    async fn queue_len(&self) -> Result<usize, StorageError>;
+    async fn cancel(&self, task_id: &str) -> Result<bool, StorageError>;
+    async fn clear(&self) -> Result<(), StorageError>;
+}
+
+/// Default backend: everything lives in process memory and is lost on
+/// restart, matching the crate's original behavior.
+#[derive(Default)]
+pub struct InMemoryBackend {
+    queue: RwLock<Vec<StoredTask>>,
+    kv: RwLock<HashMap<String, Vec<u8>>>,
+}
+
+impl InMemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StorageBackend for InMemoryBackend {
+    async fn enqueue(&self, task: StoredTask) -> Result<(), StorageError> {
+        self.queue.write().await.push(task);
+        Ok(())
+    }
+
+    async fn dequeue_ready(&self, now: DateTime<Utc>) -> Result<Option<StoredTask>, StorageError> {
+        let mut queue = self.queue.write().await;
+        let idx = queue
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.scheduled_at <= now)
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(i, _)| i);
+
+        Ok(idx.map(|i| queue.remove(i)))
+    }
+
+    async fn mark_done(&self, _task_id: &str) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn mark_failed(&self, _task_id: &str) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.kv.read().await.get(key).cloned())
+    }
+
+    async fn store(&self, key: &str, value: Vec<u8>) -> Result<(), StorageError> {
+        self.kv.write().await.insert(key.to_string(), value);
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StorageError> {
+        self.kv
+            .write()
+            .await
+            .remove(key)
+            .map(|_| ())
+            .ok_or_else(|| StorageError::NotFound(key.to_string()))
+    }
+
+    async fn queue_len(&self) -> Result<usize, StorageError> {
+        Ok(self.queue.read().await.len())
+    }
+
+    async fn cancel(&self, task_id: &str) -> Result<bool, StorageError> {
+        let mut queue = self.queue.write().await;
+        let before = queue.len();
+        queue.retain(|t| t.id != task_id);
+        Ok(queue.len() != before)
+    }
+
+    async fn clear(&self) -> Result<(), StorageError> {
+        self.queue.write().await.clear();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FileState {
+    tasks: Vec<StoredTask>,
+    kv: HashMap<String, Vec<u8>>,
+}
+
+/// Durable backend that mirrors its state into a single JSON file, so queued
+/// tasks and memory blocks survive a process restart. The file is read once,
+/// in `new`, and rewritten whole from this instance's copy on every change:
+/// instances pointed at the same path do not see each other's changes, and
+/// the last write wins.
+pub struct FileBackend {
+    path: PathBuf,
+    state: RwLock<FileState>,
+}
+
+impl FileBackend {
+    pub async fn new(path: impl Into<PathBuf>) -> Result<Self, StorageError> {
+        let path = path.into();
+        let state = match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| StorageError::Serialization(e.to_string()))?,
+            Err(_) => FileState::default(),
+        };
+
+        Ok(Self {
+            path,
+            state: RwLock::new(state),
+        })
+    }
+
+    async fn persist(&self, state: &FileState) -> Result<(), StorageError> {
+        let bytes = serde_json::to_vec_pretty(state)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+
+        tokio::fs::write(&self.path, bytes)
+            .await
+            .map_err(|e| StorageError::Io(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl StorageBackend for FileBackend {
+    async fn enqueue(&self, task: StoredTask) -> Result<(), StorageError> {
+        let mut state = self.state.write().await;
+        state.tasks.push(task);
+        self.persist(&state).await
+    }
+
+    async fn dequeue_ready(&self, now: DateTime<Utc>) -> Result<Option<StoredTask>, StorageError> {
+        let mut state = self.state.write().await;
+        let idx = state
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.scheduled_at <= now)
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(i, _)| i);
+
+        let task = idx.map(|i| state.tasks.remove(i));
+        if task.is_some() {
+            self.persist(&state).await?;
+        }
+        Ok(task)
+    }
+
+    async fn mark_done(&self, _task_id: &str) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn mark_failed(&self, _task_id: &str) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn load(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        Ok(self.state.read().await.kv.get(key).cloned())
+    }
+
+    async fn store(&self, key: &str, value: Vec<u8>) -> Result<(), StorageError> {
+        let mut state = self.state.write().await;
+        state.kv.insert(key.to_string(), value);
+        self.persist(&state).await
+    }
+
+    async fn remove(&self, key: &str) -> Result<(), StorageError> {
+        let mut state = self.state.write().await;
+        state
+            .kv
+            .remove(key)
+            .ok_or_else(|| StorageError::NotFound(key.to_string()))?;
+        self.persist(&state).await
+    }
+
+    async fn queue_len(&self) -> Result<usize, StorageError> {
+        Ok(self.state.read().await.tasks.len())
+    }
+
+    async fn cancel(&self, task_id: &str) -> Result<bool, StorageError> {
+        let mut state = self.state.write().await;
+        let before = state.tasks.len();
+        state.tasks.retain(|t| t.id != task_id);
+        let changed = state.tasks.len() != before;
+        if changed {
+            self.persist(&state).await?;
+        }
+        Ok(changed)
+    }
+
+    async fn clear(&self) -> Result<(), StorageError> {
+        let mut state = self.state.write().await;
+        state.tasks.clear();
+        self.persist(&state).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_task(id: &str, priority: u8, offset_secs: i64) -> StoredTask {
+        StoredTask {
+            id: id.to_string(),
+            priority,
+            scheduled_at: Utc::now() + chrono::Duration::seconds(offset_secs),
+            retries: 0,
+            payload: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_backend_dequeues_highest_priority_ready_task() {
+        let backend = InMemoryBackend::new();
+        backend.enqueue(sample_task("low", 1, -1)).await.unwrap();
+        backend.enqueue(sample_task("high", 5, -1)).await.unwrap();
+        backend.enqueue(sample_task("future", 9, 60)).await.unwrap();
+        assert_eq!(backend.queue_len().await.unwrap(), 3);
+
+        let next = backend.dequeue_ready(Utc::now()).await.unwrap().unwrap();
+        assert_eq!(next.id, "high");
+        assert_eq!(backend.queue_len().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_backend_kv_roundtrip_and_cancel() {
+        let backend = InMemoryBackend::new();
+        backend.store("key", b"value".to_vec()).await.unwrap();
+        assert_eq!(backend.load("key").await.unwrap(), Some(b"value".to_vec()));
+
+        backend.remove("key").await.unwrap();
+        assert!(backend.load("key").await.unwrap().is_none());
+
+        backend.enqueue(sample_task("cancel-me", 1, -1)).await.unwrap();
+        assert!(backend.cancel("cancel-me").await.unwrap());
+        assert_eq!(backend.queue_len().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_round_trips_state_through_disk() {
+        let path = std::env::temp_dir().join(format!("scheduler_storage_test_{}.json", std::process::id()));
+        let _ = tokio::fs::remove_file(&path).await;
+
+        {
+            let backend = FileBackend::new(path.clone()).await.unwrap();
+            backend.enqueue(sample_task("durable", 1, -1)).await.unwrap();
+            backend.store("key", b"value".to_vec()).await.unwrap();
+        }
+
+        let reopened = FileBackend::new(path.clone()).await.unwrap();
+        assert_eq!(reopened.queue_len().await.unwrap(), 1);
+        assert_eq!(reopened.load("key").await.unwrap(), Some(b"value".to_vec()));
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+}